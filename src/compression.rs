@@ -0,0 +1,312 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use crate::{body::Filler, Size};
+use bytes::Bytes;
+use http_body::{Body, Frame, SizeHint};
+use std::{
+    convert::Infallible,
+    fmt,
+    io::{Read as _, Write as _},
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// A content coding negotiated from a request's `Accept-Encoding` header.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Encoding {
+    /// Brotli (`br`).
+    Brotli,
+
+    /// Gzip (`gzip`).
+    Gzip,
+
+    /// DEFLATE (`deflate`).
+    Deflate,
+
+    /// No coding applied.
+    #[default]
+    Identity,
+}
+
+impl Encoding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Encoding::Brotli => "br",
+            Encoding::Gzip => "gzip",
+            Encoding::Deflate => "deflate",
+            Encoding::Identity => "identity",
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The preference order tried against a request's `Accept-Encoding` header.
+pub const DEFAULT_PREFERENCE: [Encoding; 3] = [Encoding::Brotli, Encoding::Gzip, Encoding::Deflate];
+
+/// Picks the first encoding in `preference` that the client's `Accept-Encoding` header allows.
+///
+/// Returns [`Encoding::Identity`] when `accept_encoding` is absent or none of the preferred
+/// codecs are offered. A `q=0` parameter marks a coding as explicitly refused, even under `*`.
+pub fn negotiate(accept_encoding: Option<&str>, preference: &[Encoding]) -> Encoding {
+    let Some(header) = accept_encoding else {
+        return Encoding::Identity;
+    };
+
+    let offered: Vec<(&str, f32)> = header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let name = parts.next()?.trim();
+            if name.is_empty() {
+                return None;
+            }
+            let q = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|v| v.parse::<f32>().ok())
+                .unwrap_or(1.0);
+            Some((name, q))
+        })
+        .collect();
+
+    let is_offered =
+        |name: &str| offered.iter().any(|(o, q)| *q > 0.0 && o.eq_ignore_ascii_case(name));
+
+    if is_offered("*") {
+        if let Some(first) = preference.iter().find(|enc| {
+            !offered
+                .iter()
+                .any(|(o, q)| *q <= 0.0 && o.eq_ignore_ascii_case(enc.as_str()))
+        }) {
+            return *first;
+        }
+    }
+
+    preference
+        .iter()
+        .copied()
+        .find(|enc| is_offered(enc.as_str()))
+        .unwrap_or_default()
+}
+
+/// Compresses `data` using `encoding`, returning `data` unchanged for [`Encoding::Identity`].
+pub fn compress(data: &[u8], encoding: Encoding) -> std::io::Result<Vec<u8>> {
+    match encoding {
+        Encoding::Brotli => {
+            let mut out = Vec::new();
+            let mut writer = brotli::CompressorWriter::new(&mut out, 4096, 5, 22);
+            writer.write_all(data)?;
+            drop(writer);
+            Ok(out)
+        }
+        Encoding::Gzip => {
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+        Encoding::Deflate => {
+            let mut encoder =
+                flate2::write::DeflateEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(data)?;
+            encoder.finish()
+        }
+        Encoding::Identity => Ok(data.to_vec()),
+    }
+}
+
+/// Size of each chunk read from the encoder while streaming a [`CompressedBody`].
+const READ_CHUNK: usize = 64 * 1024;
+
+enum Reader {
+    Brotli(Box<brotli::CompressorReader<Filler>>),
+    Gzip(flate2::read::GzEncoder<Filler>),
+    Deflate(flate2::read::DeflateEncoder<Filler>),
+}
+
+impl std::io::Read for Reader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Reader::Brotli(reader) => reader.read(buf),
+            Reader::Gzip(reader) => reader.read(buf),
+            Reader::Deflate(reader) => reader.read(buf),
+        }
+    }
+}
+
+/// An [`http_body::Body`] that compresses a deterministically generated body on the fly.
+///
+/// Unlike [`compress`], which requires the whole plaintext body up front, this pulls [`Filler`]
+/// bytes through the encoder a chunk at a time, so compressing a large generated response never
+/// buffers it all in memory.
+pub struct CompressedBody {
+    reader: Reader,
+    done: bool,
+}
+
+impl CompressedBody {
+    /// Creates a body that compresses `size` bytes of deterministic filler using `encoding`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `encoding` is [`Encoding::Identity`]; serve an uncompressed body instead of
+    /// wrapping it in a `CompressedBody` in that case.
+    pub fn new(size: Size, encoding: Encoding) -> Self {
+        let filler = Filler::new(size);
+        let reader = match encoding {
+            Encoding::Brotli => {
+                Reader::Brotli(Box::new(brotli::CompressorReader::new(filler, 4096, 5, 22)))
+            }
+            Encoding::Gzip => {
+                Reader::Gzip(flate2::read::GzEncoder::new(filler, flate2::Compression::default()))
+            }
+            Encoding::Deflate => Reader::Deflate(flate2::read::DeflateEncoder::new(
+                filler,
+                flate2::Compression::default(),
+            )),
+            Encoding::Identity => panic!("CompressedBody does not support Encoding::Identity"),
+        };
+        Self {
+            reader,
+            done: false,
+        }
+    }
+}
+
+impl Body for CompressedBody {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        let mut buf = vec![0u8; READ_CHUNK];
+        let n = self
+            .reader
+            .read(&mut buf)
+            .expect("compressing deterministic filler bytes cannot fail");
+        if n == 0 {
+            self.done = true;
+            return Poll::Ready(None);
+        }
+
+        buf.truncate(n);
+        Poll::Ready(Some(Ok(Frame::data(Bytes::from(buf)))))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.done
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_prefers_first_match() {
+        let encoding = negotiate(Some("gzip, br"), &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Brotli);
+    }
+
+    #[test]
+    fn negotiate_none_offered() {
+        let encoding = negotiate(Some("identity"), &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Identity);
+    }
+
+    #[test]
+    fn negotiate_missing_header() {
+        let encoding = negotiate(None, &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Identity);
+    }
+
+    #[test]
+    fn negotiate_wildcard() {
+        let encoding = negotiate(Some("*"), &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Brotli);
+    }
+
+    #[test]
+    fn negotiate_q_zero_rejects_encoding() {
+        let encoding = negotiate(Some("br;q=0, gzip"), &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Gzip);
+    }
+
+    #[test]
+    fn negotiate_q_zero_under_wildcard() {
+        let encoding = negotiate(Some("*, br;q=0"), &DEFAULT_PREFERENCE);
+        assert_eq!(encoding, Encoding::Gzip);
+    }
+
+    #[test]
+    fn compress_identity_is_unchanged() {
+        let out = compress(b"hello", Encoding::Identity).unwrap();
+        assert_eq!(out, b"hello");
+    }
+
+    #[test]
+    fn compress_gzip_round_trips() {
+        let compressed = compress(b"hello, world!", Encoding::Gzip).unwrap();
+        let mut decoder = flate2::read::GzDecoder::new(compressed.as_slice());
+        let mut out = String::new();
+        decoder.read_to_string(&mut out).unwrap();
+        assert_eq!(out, "hello, world!");
+    }
+
+    // `poll_frame` never actually returns `Pending`, so a waker that does nothing is enough to
+    // drive it synchronously from a plain `#[test]`.
+    fn noop_waker() -> std::task::Waker {
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        fn clone(_: *const ()) -> RawWaker {
+            raw()
+        }
+        fn noop(_: *const ()) {}
+        fn raw() -> RawWaker {
+            static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, noop, noop, noop);
+            RawWaker::new(std::ptr::null(), &VTABLE)
+        }
+        unsafe { Waker::from_raw(raw()) }
+    }
+
+    fn collect(mut body: CompressedBody) -> Vec<u8> {
+        let waker = noop_waker();
+        let mut cx = Context::from_waker(&waker);
+        let mut out = Vec::new();
+        while let Poll::Ready(Some(Ok(frame))) = Pin::new(&mut body).poll_frame(&mut cx) {
+            out.extend_from_slice(frame.into_data().unwrap().as_ref());
+        }
+        out
+    }
+
+    #[test]
+    fn compressed_body_gzip_round_trips() {
+        let body = CompressedBody::new(Size::from(10_000u64), Encoding::Gzip);
+        let compressed = collect(body);
+
+        let mut decoder = flate2::read::GzDecoder::new(compressed.as_slice());
+        let mut out = Vec::new();
+        decoder.read_to_end(&mut out).unwrap();
+        assert_eq!(out.len(), 10_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn compressed_body_rejects_identity() {
+        CompressedBody::new(Size::from(1u64), Encoding::Identity);
+    }
+}
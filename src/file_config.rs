@@ -0,0 +1,139 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use crate::{Bind, ColorChoice, Protocol};
+use serde::Deserialize;
+use serve::{Compression, Route, Size};
+use std::{path::Path, time::Duration};
+
+/// Server configuration loaded from `--config`, describing the same knobs as [`Args`](crate::Args).
+///
+/// CLI flags take precedence over any value set here; unset fields fall back to the server's
+/// built-in defaults.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct FileConfig {
+    pub port: Option<u16>,
+
+    #[serde(default, deserialize_with = "deserialize_opt_bind")]
+    pub bind: Option<Bind>,
+
+    pub color: Option<ColorChoice>,
+    pub http: Option<Protocol>,
+    pub block_size: Option<Size>,
+    pub size: Option<Size>,
+    pub compression: Option<Compression>,
+    pub min_size: Option<Size>,
+
+    #[serde(default, deserialize_with = "deserialize_opt_duration")]
+    pub timeout: Option<Duration>,
+
+    #[serde(default, deserialize_with = "deserialize_routes")]
+    pub routes: Vec<Route>,
+
+    pub strict: Option<bool>,
+}
+
+impl FileConfig {
+    /// Reads and parses a config file, choosing JSON or TOML based on its extension.
+    ///
+    /// Any extension other than `.json` is treated as TOML.
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
+        let content = std::fs::read_to_string(path)?;
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Ok(toml::from_str(&content)?)
+        }
+    }
+}
+
+fn deserialize_opt_bind<'de, D>(deserializer: D) -> Result<Option<Bind>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_opt_duration<'de, D>(deserializer: D) -> Result<Option<Duration>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|s| humantime::parse_duration(&s).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn deserialize_routes<'de, D>(deserializer: D) -> Result<Vec<Route>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .into_iter()
+        .map(|s| s.parse().map_err(serde::de::Error::custom))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_config_toml_empty() {
+        let config: FileConfig = toml::from_str("").unwrap();
+        assert_eq!(config.port, None);
+        assert!(config.routes.is_empty());
+    }
+
+    #[test]
+    fn file_config_toml_full() {
+        let config: FileConfig = toml::from_str(
+            r#"
+            port = 8080
+            bind = "127.0.0.1:9000"
+            color = "always"
+            http = "2"
+            block-size = "64kib"
+            size = "1mb"
+            compression = "always"
+            min-size = 1024
+            timeout = "30s"
+            routes = ["/big=>size=100mb"]
+            strict = true
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.port, Some(8080));
+        assert!(matches!(config.bind, Some(Bind::Tcp(_))));
+        assert_eq!(config.color, Some(ColorChoice::Always));
+        assert_eq!(config.http, Some(Protocol::Http2));
+        assert_eq!(*config.block_size.unwrap(), 64 * 1024);
+        assert_eq!(*config.size.unwrap(), 1_000_000);
+        assert_eq!(config.compression, Some(Compression::Always));
+        assert_eq!(*config.min_size.unwrap(), 1024);
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.routes.len(), 1);
+        assert_eq!(config.routes[0].prefix, "/big");
+        assert_eq!(config.strict, Some(true));
+    }
+
+    #[test]
+    fn file_config_json_full() {
+        let config: FileConfig = serde_json::from_str(
+            r#"{"port": 8080, "bind": "unix:/tmp/serve.sock", "min-size": 0}"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.port, Some(8080));
+        assert!(matches!(config.bind, Some(Bind::Unix(_))));
+        assert_eq!(*config.min_size.unwrap(), 0);
+    }
+
+    #[test]
+    fn file_config_rejects_invalid_route() {
+        assert!(toml::from_str::<FileConfig>(r#"routes = ["no-separator"]"#).is_err());
+    }
+}
@@ -1,18 +1,225 @@
 // Copyright 2025 Heath Stewart.
 // Licensed under the MIT License. See LICENSE.txt in the project root for license information.
 
+mod body;
+mod compression;
+mod request_spec;
+mod route;
 mod size;
 
-use hyper::{body::Incoming, Request, Response};
+use body::ChunkedBody;
+use bytes::Bytes;
+pub use compression::{CompressedBody, Encoding};
+use http_body_util::{Either, Full};
+use hyper::{body::Incoming, header, Request, Response, StatusCode};
+pub use request_spec::*;
+pub use route::*;
 pub use size::*;
-use std::time::Duration;
+use std::{fmt, str::FromStr};
+
+/// Configuration controlling how [`serve`] generates a response.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// Total size of the response body to generate.
+    ///
+    /// When `None`, `serve` falls back to the static `"hello, world!"` greeting.
+    pub size: Option<Size>,
+
+    /// Size of each chunk written while streaming a generated body.
+    ///
+    /// Defaults to `size` (a single chunk) when not set.
+    pub block_size: Option<Size>,
+
+    /// When to compress the generated response body.
+    pub compression: Compression,
+
+    /// Body size below which compression is skipped even when negotiated.
+    ///
+    /// Ignored when `compression` is [`Compression::Always`].
+    pub min_size: Option<Size>,
+
+    /// Path-prefix routes overriding the response for matching requests, longest prefix wins.
+    pub routes: Vec<Route>,
+
+    /// Respond `404 Not Found` to requests that don't match any `routes`.
+    pub strict: bool,
+}
+
+/// Controls whether [`serve`] compresses a generated response body.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    /// Compress whenever the client negotiates a supported encoding and the body meets
+    /// `min_size`.
+    #[default]
+    Auto,
+
+    /// Compress whenever the client negotiates a supported encoding, ignoring `min_size`.
+    Always,
+
+    /// Never compress, regardless of `Accept-Encoding`.
+    Never,
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Compression::Auto => f.write_str("auto"),
+            Compression::Always => f.write_str("always"),
+            Compression::Never => f.write_str("never"),
+        }
+    }
+}
+
+impl FromStr for Compression {
+    type Err = ParseCompressionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Compression::Auto),
+            "always" => Ok(Compression::Always),
+            "never" => Ok(Compression::Never),
+            _ => Err(ParseCompressionError(s.to_string())),
+        }
+    }
+}
+
+/// Error returned by [`Compression::from_str()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseCompressionError(String);
+
+impl fmt::Display for ParseCompressionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid compression mode `{}`; expected auto, always, or never",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseCompressionError {}
+
+/// The response body types [`serve`] can answer a request with.
+type ServeBody = Either<Full<Bytes>, Either<ChunkedBody, CompressedBody>>;
 
 pub async fn serve(
-    _req: Request<Incoming>,
-) -> Result<Response<String>, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    tokio::time::sleep(Duration::from_millis(100000)).await;
+    config: Config,
+    req: Request<Incoming>,
+) -> Result<Response<ServeBody>, Box<dyn std::error::Error + Send + Sync + 'static>> {
+    let spec = match parse_query(req.uri().query()) {
+        Ok(spec) => spec,
+        Err(response) => return Ok(*response),
+    };
+
+    let route = route::longest_match(&config.routes, req.uri().path());
+    let route_spec = route.map(|route| route.spec);
+
+    // Replaces the old hardcoded 100s startup delay: now configurable per request/route, and
+    // zero (no delay) unless a `delay` is explicitly set.
+    let delay = spec.delay.or(route_spec.and_then(|s| s.delay));
+    tokio::time::sleep(delay.unwrap_or_default()).await;
+
+    if route.is_none() && config.strict {
+        return Ok(Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .header("content-type", "text/plain")
+            .body(Either::Left(Full::new(Bytes::from_static(b"not found"))))?);
+    }
+
+    let status = spec
+        .status
+        .or(route_spec.and_then(|s| s.status))
+        .unwrap_or(StatusCode::OK);
+    let size = spec
+        .size
+        .or(route_spec.and_then(|s| s.size))
+        .or(config.size);
+    let Some(size) = size else {
+        return Ok(Response::builder()
+            .status(status)
+            .header("content-type", "text/plain")
+            .body(Either::Left(Full::new(Bytes::from_static(b"hello, world!"))))?);
+    };
+
+    if config.compression != Compression::Never {
+        let accept_encoding = req
+            .headers()
+            .get(header::ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok());
+        let encoding = compression::negotiate(accept_encoding, &compression::DEFAULT_PREFERENCE);
+        let min_size = config.min_size.map(|s| *s).unwrap_or(0);
+
+        if encoding != Encoding::Identity
+            && (config.compression == Compression::Always || *size >= min_size)
+        {
+            return Ok(Response::builder()
+                .status(status)
+                .header("content-type", "application/octet-stream")
+                .header(header::CONTENT_ENCODING, encoding.to_string())
+                .header(header::VARY, "accept-encoding")
+                .body(Either::Right(Either::Right(CompressedBody::new(
+                    size, encoding,
+                ))))?);
+        }
+    }
+
+    let block_size = spec
+        .block
+        .or(route_spec.and_then(|s| s.block))
+        .or(config.block_size)
+        .unwrap_or(size);
     Ok(Response::builder()
-        .status(200)
-        .header("content-type", "text/plain")
-        .body("hello, world!".into())?)
+        .status(status)
+        .header("content-type", "application/octet-stream")
+        .body(Either::Right(Either::Left(ChunkedBody::new(
+            size, block_size,
+        ))))?)
+}
+
+/// Parses a request's query string into a [`RequestSpec`].
+///
+/// A missing query string yields the default spec. A malformed one yields `Err` with a
+/// `400 Bad Request` response describing the problem, rather than a parse error: hyper doesn't
+/// turn a [`Service`](hyper::service::Service) error into a response, it resets the connection,
+/// which is a poor experience for a client hand-crafting a query string.
+fn parse_query(query: Option<&str>) -> Result<RequestSpec, Box<Response<ServeBody>>> {
+    query
+        .map(str::parse)
+        .transpose()
+        .map(Option::unwrap_or_default)
+        .map_err(|err: ParseRequestSpecError| {
+            Box::new(
+                Response::builder()
+                    .status(StatusCode::BAD_REQUEST)
+                    .header("content-type", "text/plain")
+                    .body(Either::Left(Full::new(Bytes::from(err.to_string()))))
+                    .expect("building a bad request response from static headers cannot fail"),
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http_body_util::BodyExt;
+
+    #[tokio::test]
+    async fn parse_query_missing_defaults() {
+        let Ok(spec) = parse_query(None) else {
+            panic!("missing query string should not produce a response");
+        };
+        assert_eq!(spec.size, None);
+    }
+
+    #[tokio::test]
+    async fn parse_query_rejects_malformed_query() {
+        let Err(response) = parse_query(Some("size=not-a-size")) else {
+            panic!("malformed query string should be rejected");
+        };
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, Bytes::from_static(b"invalid size"));
+    }
 }
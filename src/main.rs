@@ -1,17 +1,235 @@
 // Copyright 2025 Heath Stewart.
 // Licensed under the MIT License. See LICENSE.txt in the project root for license information.
 
+mod file_config;
+
 use anstream::AutoStream;
 use anstyle::Style;
+use bytes::Bytes;
 use clap::{
     builder::{styling::AnsiColor, PossibleValue, Styles},
     Parser, ValueEnum,
 };
-use hyper::{server::conn::http1, service::service_fn};
-use hyper_util::{rt::TokioIo, server::graceful::GracefulShutdown};
-use serve::Size;
-use std::{fmt, io::Write as _, net::SocketAddr, pin::pin, time::Duration};
-use tokio::{net::TcpListener, time};
+use file_config::FileConfig;
+use hyper::{
+    server::conn::{http1, http2},
+    service::service_fn,
+};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::graceful::GracefulShutdown,
+};
+use serde::Deserialize;
+use serve::{Compression, Route, Size};
+use std::{
+    fmt,
+    io::Write as _,
+    net::SocketAddr,
+    path::PathBuf,
+    pin::{pin, Pin},
+    str::FromStr,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream, UnixListener, UnixStream},
+    time,
+};
+use tokio_util::either::Either as Conn;
+
+/// The HTTP/2 connection preface a client sends when using prior-knowledge h2.
+const H2_PREFACE: &[u8] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// How long to wait for the full preface to arrive before giving up and falling back to
+/// HTTP/1.1.
+const PREFACE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Wraps `S`, replaying `prefix` to the first reads before delegating to `S`.
+///
+/// Used to sniff the start of a connection (e.g. [`sniff_http2_preface`]) without discarding
+/// whatever bytes were read off the wire while doing so: `S::peek` looked like the obvious tool
+/// for this, but a peek that returns fewer bytes than requested doesn't leave the socket in a
+/// state where waiting for readability is guaranteed to wait for *new* data, since nothing was
+/// actually consumed. Reading (and buffering) the bytes instead relies on ordinary read
+/// readiness, which tokio does correctly clear and reassert.
+struct Prefixed<S> {
+    prefix: Bytes,
+    inner: S,
+}
+
+impl<S> Prefixed<S> {
+    fn new(inner: S, prefix: Vec<u8>) -> Self {
+        Self {
+            prefix: Bytes::from(prefix),
+            inner,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Prefixed<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if !self.prefix.is_empty() {
+            let len = buf.remaining().min(self.prefix.len());
+            let chunk = self.prefix.split_to(len);
+            buf.put_slice(&chunk);
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Prefixed<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Reads up to `preface.len()` bytes from `stream` to check whether they match `preface`,
+/// buffering whatever was read so it can be replayed to the first read of the returned
+/// `Prefixed` stream.
+///
+/// Gives up after `timeout` and treats however much arrived as a non-match, so a client that
+/// never sends the full preface doesn't hang the connection open. Unlike peeking, this actually
+/// consumes the bytes off the socket, so waiting for the rest of a short read genuinely waits
+/// for new data instead of immediately waking on readiness left over from the short read.
+async fn sniff_preface<S: AsyncRead + Unpin>(
+    mut stream: S,
+    preface: &[u8],
+    timeout: Duration,
+) -> std::io::Result<(bool, Prefixed<S>)> {
+    let mut buf = vec![0u8; preface.len()];
+    let mut filled = 0;
+
+    let result = time::timeout(timeout, async {
+        while filled < buf.len() {
+            let n = stream.read(&mut buf[filled..]).await?;
+            if n == 0 {
+                break;
+            }
+            filled += n;
+        }
+        Ok::<_, std::io::Error>(())
+    })
+    .await;
+
+    if let Ok(Err(err)) = result {
+        return Err(err);
+    }
+
+    buf.truncate(filled);
+    let is_match = buf == preface;
+    Ok((is_match, Prefixed::new(stream, buf)))
+}
+
+/// Reads the start of `stream` to detect the HTTP/2 connection preface a client sends when using
+/// prior-knowledge h2, without losing whatever bytes were read while detecting it.
+///
+/// Always reports `false` for Unix domain sockets without reading anything from them; `--http
+/// auto` falls back to HTTP/1.1 over a UDS. Falls back to HTTP/1.1 if the full preface hasn't
+/// arrived within [`PREFACE_TIMEOUT`].
+async fn sniff_http2_preface(
+    stream: Conn<TcpStream, UnixStream>,
+) -> std::io::Result<(bool, Prefixed<Conn<TcpStream, UnixStream>>)> {
+    if matches!(stream, Conn::Right(_)) {
+        return Ok((false, Prefixed::new(stream, Vec::new())));
+    }
+
+    sniff_preface(stream, H2_PREFACE, PREFACE_TIMEOUT).await
+}
+
+/// Where to accept connections: a TCP address or a Unix domain socket.
+#[derive(Clone, Debug)]
+pub(crate) enum Bind {
+    Tcp(SocketAddr),
+    Unix(PathBuf),
+}
+
+impl fmt::Display for Bind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bind::Tcp(addr) => write!(f, "{addr}"),
+            Bind::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+impl FromStr for Bind {
+    type Err = ParseBindError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(path) = s.strip_prefix("unix:") {
+            return Ok(Bind::Unix(PathBuf::from(path)));
+        }
+        s.parse::<SocketAddr>()
+            .map(Bind::Tcp)
+            .map_err(|_| ParseBindError(s.to_string()))
+    }
+}
+
+/// Error returned by [`Bind::from_str()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct ParseBindError(String);
+
+impl fmt::Display for ParseBindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid bind address `{}`; expected host:port or unix:/path",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for ParseBindError {}
+
+/// A listener accepting either TCP or Unix domain socket connections.
+enum Listener {
+    Tcp(TcpListener),
+    Unix(UnixListener),
+}
+
+impl Listener {
+    async fn bind(bind: &Bind) -> std::io::Result<Self> {
+        match bind {
+            Bind::Tcp(addr) => Ok(Listener::Tcp(TcpListener::bind(addr).await?)),
+            Bind::Unix(path) => {
+                // Remove a stale socket file left behind by a previous, uncleanly shut down run.
+                let _ = std::fs::remove_file(path);
+                Ok(Listener::Unix(UnixListener::bind(path)?))
+            }
+        }
+    }
+
+    async fn accept(&self) -> std::io::Result<(Conn<TcpStream, UnixStream>, String)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Conn::Left(stream), addr.to_string()))
+            }
+            Listener::Unix(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Conn::Right(stream), format!("{addr:?}")))
+            }
+        }
+    }
+}
 
 const CLAP_V3_STYLES: Styles = Styles::styled()
     .error(AnsiColor::Red.on_default())
@@ -23,17 +241,52 @@ const CLAP_V3_STYLES: Styles = Styles::styled()
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
-    let choice = args.color.into();
+    let file = match &args.config {
+        Some(path) => FileConfig::load(path)?,
+        None => FileConfig::default(),
+    };
+
+    let color = args.color.or(file.color).unwrap_or_default();
+    let http = args.http.or(file.http).unwrap_or_default();
+    let compression = args.compression.or(file.compression).unwrap_or_default();
+    let port = args.port.or(file.port).unwrap_or(4000);
+    let timeout = args.timeout.or(file.timeout);
+
+    let choice = color.into();
     let mut stdout = AutoStream::new(std::io::stdout(), choice);
     let mut stderr = AutoStream::new(std::io::stderr(), choice);
     let success = anstyle::Style::new().fg_color(Some(AnsiColor::Green.into()));
     let warning = anstyle::Style::new().fg_color(Some(AnsiColor::Yellow.into()));
     let error = Style::new().fg_color(Some(AnsiColor::Red.into()));
 
-    let addr = SocketAddr::from(([127, 0, 0, 1], args.port));
-    let listener = TcpListener::bind(addr).await?;
-    writeln!(stdout, "{success}Listening on http://{addr}{success:#}")?;
-    if let Some(timeout) = args.timeout {
+    // CLI routes are appended after the file's so they win `longest_match` ties on identical
+    // prefixes, matching how every other flag lets the CLI override the config file.
+    let routes: Vec<Route> = file.routes.into_iter().chain(args.route).collect();
+    let strict = args.strict || file.strict.unwrap_or(false);
+
+    let config = serve::Config {
+        size: args.size.or(file.size),
+        block_size: args.block_size.or(file.block_size),
+        compression,
+        min_size: args.min_size.or(file.min_size),
+        routes,
+        strict,
+    };
+
+    let bind = args
+        .bind
+        .or(file.bind)
+        .unwrap_or_else(|| Bind::Tcp(SocketAddr::from(([127, 0, 0, 1], port))));
+    let listener = Listener::bind(&bind).await?;
+    match &bind {
+        Bind::Tcp(addr) => writeln!(stdout, "{success}Listening on http://{addr}{success:#}")?,
+        Bind::Unix(path) => writeln!(
+            stdout,
+            "{success}Listening on unix:{}{success:#}",
+            path.display()
+        )?,
+    }
+    if let Some(timeout) = timeout {
         writeln!(
             stdout,
             "Shutting down in {}, or press Ctrl+C",
@@ -54,20 +307,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     loop {
         tokio::select! {
             Ok((stream, source)) = listener.accept() => {
-                writeln!(stdout, "Request from {source:?}")?;
+                writeln!(stdout, "Request from {source}")?;
 
-                let io = TokioIo::new(stream);
-                let conn = http1::Builder::new().serve_connection(io, service_fn(serve::serve));
-                let watcher = graceful.watch(conn);
+                let (use_http2, stream) = match http {
+                    Protocol::Http1 => (false, Prefixed::new(stream, Vec::new())),
+                    Protocol::Http2 => (true, Prefixed::new(stream, Vec::new())),
+                    Protocol::Auto => sniff_http2_preface(stream).await?,
+                };
 
-                tokio::task::spawn(watcher);
+                let io = TokioIo::new(stream);
+                let config = config.clone();
+                if use_http2 {
+                    let conn = http2::Builder::new(TokioExecutor::new())
+                        .serve_connection(io, service_fn(move |req| serve::serve(config.clone(), req)));
+                    tokio::task::spawn(graceful.watch(conn));
+                } else {
+                    let conn = http1::Builder::new()
+                        .serve_connection(io, service_fn(move |req| serve::serve(config.clone(), req)));
+                    tokio::task::spawn(graceful.watch(conn));
+                }
             },
             _ = &mut signal => {
                 writeln!(stdout, "{warning}Shutting down...{warning:#}")?;
                 break;
             },
-            _ = time::sleep(args.timeout.unwrap_or_default()), if args.timeout.is_some() => {
-                writeln!(stderr, "{warning}Shutting down after {} timeout...{warning:#}", humantime::format_duration(args.timeout.unwrap()))?;
+            _ = time::sleep(timeout.unwrap_or_default()), if timeout.is_some() => {
+                writeln!(stderr, "{warning}Shutting down after {} timeout...{warning:#}", humantime::format_duration(timeout.unwrap()))?;
                 break;
             }
         };
@@ -80,37 +345,93 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         },
         _ = time::sleep(Duration::from_secs(3)) => {
             writeln!(stderr, "{error}Timed out waiting for connections to close{error:#}")?;
+            if let Bind::Unix(path) = &bind {
+                let _ = std::fs::remove_file(path);
+            }
             std::process::exit(1);
         }
     }
 
+    if let Bind::Unix(path) = &bind {
+        let _ = std::fs::remove_file(path);
+    }
+
     Ok(())
 }
 
 #[derive(Debug, Parser)]
 #[command(author, version, styles = CLAP_V3_STYLES)]
 struct Args {
-    /// The size of blocks of the response to send e.g., "32kb", "1 mib", etc.
+    /// Address to bind: a `host:port` TCP address (any interface, IPv6 included) or
+    /// `unix:/path/to.sock` for a Unix domain socket.
     ///
-    /// Supports bytes ("b") through petabytes ("pb") and pebibytes ("pib").
+    /// Overrides `--port` when set. Takes precedence over any `bind` set via `--config`.
+    #[arg(long)]
+    pub bind: Option<Bind>,
+
+    /// The size of blocks to stream the response body in e.g., "32kb", "1 mib", etc.
+    ///
+    /// Supports bytes ("b") through petabytes ("pb") and pebibytes ("pib"). Defaults to `size`
+    /// (a single chunk) when `size` is set.
     #[arg(short = 'b', long)]
     pub block_size: Option<Size>,
 
+    /// The total size of the response body to generate e.g., "32kb", "1 mib", etc.
+    ///
+    /// When not set, the server responds with the static "hello, world!" greeting.
+    #[arg(short = 's', long)]
+    pub size: Option<Size>,
+
+    /// Load server configuration from a TOML or JSON file, describing the same knobs as these
+    /// flags. Explicit flags on the command line take precedence over the file's values.
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
     /// When to show color output.
-    #[arg(long, default_value_t = ColorChoice::default())]
-    pub color: ColorChoice,
+    #[arg(long)]
+    pub color: Option<ColorChoice>,
+
+    /// When to compress the generated response body.
+    #[arg(long)]
+    pub compression: Option<Compression>,
+
+    /// Which HTTP protocol version(s) to serve.
+    ///
+    /// In "auto" mode, the server reads the start of each TCP connection to detect the HTTP/2
+    /// connection preface; Unix domain socket connections are always served as HTTP/1.1.
+    #[arg(long)]
+    pub http: Option<Protocol>,
 
-    /// Port to bind.
-    #[arg(short = 'p', long, default_value_t = 4000)]
-    pub port: u16,
+    /// Body size below which compression is skipped e.g., "1kb", "64 kib".
+    ///
+    /// Ignored when `--compression always` is set.
+    #[arg(long)]
+    pub min_size: Option<Size>,
+
+    /// Port to bind. Ignored when `--bind` is set.
+    #[arg(short = 'p', long)]
+    pub port: Option<u16>,
 
     /// When to shut down the service e.g., 500ms, 10s, "1 hour", etc.
     #[arg(long, value_parser = humantime::parse_duration)]
     pub timeout: Option<Duration>,
+
+    /// Maps a path prefix to response overrides e.g., `/big=>size=100mb,block=64kib`. May be
+    /// repeated. The longest matching prefix wins, with a `--route` breaking a tie against a
+    /// same-length prefix from `routes` set via `--config`; unmatched requests fall back to
+    /// `--size` and friends. Adds to, rather than replacing, any `routes` set via `--config`.
+    #[arg(long = "route")]
+    pub route: Vec<Route>,
+
+    /// Respond `404 Not Found` to requests that don't match any `--route` or any `routes` set
+    /// via `--config`.
+    #[arg(long)]
+    pub strict: bool,
 }
 
-#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
-enum ColorChoice {
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ColorChoice {
     #[default]
     Auto,
     Always,
@@ -150,3 +471,105 @@ impl ValueEnum for ColorChoice {
         })
     }
 }
+
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Deserialize)]
+pub(crate) enum Protocol {
+    #[serde(rename = "1")]
+    Http1,
+    #[serde(rename = "2")]
+    Http2,
+    #[default]
+    #[serde(rename = "auto")]
+    Auto,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Http1 => f.write_str("1"),
+            Protocol::Http2 => f.write_str("2"),
+            Protocol::Auto => f.write_str("auto"),
+        }
+    }
+}
+
+impl ValueEnum for Protocol {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Protocol::Http1, Protocol::Http2, Protocol::Auto]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(match self {
+            Protocol::Http1 => PossibleValue::new("1"),
+            Protocol::Http2 => PossibleValue::new("2"),
+            Protocol::Auto => PossibleValue::new("auto"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncWriteExt;
+
+    #[tokio::test]
+    async fn prefixed_replays_buffered_prefix_before_delegating() {
+        let (mut ours, theirs) = tokio::io::duplex(64);
+        let mut prefixed = Prefixed::new(theirs, b"abc".to_vec());
+
+        let mut buf = [0u8; 2];
+        prefixed.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ab");
+
+        let mut buf = [0u8; 1];
+        prefixed.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"c");
+
+        ours.write_all(b"d").await.unwrap();
+        let mut buf = [0u8; 1];
+        prefixed.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"d");
+    }
+
+    #[tokio::test]
+    async fn sniff_preface_matches_full_preface() {
+        let (mut ours, theirs) = tokio::io::duplex(64);
+        ours.write_all(H2_PREFACE).await.unwrap();
+
+        let (is_match, mut prefixed) = sniff_preface(theirs, H2_PREFACE, Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(is_match);
+
+        // The bytes already consumed while sniffing are still there for whoever reads next.
+        let mut buf = vec![0u8; H2_PREFACE.len()];
+        prefixed.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, H2_PREFACE);
+    }
+
+    // Regression test for a busy spin: a client that sends only part of the preface and then
+    // stalls used to leave the detector re-peeking the same bytes forever, because a peek that
+    // returns fewer bytes than requested doesn't clear read readiness. With paused virtual time,
+    // this test only completes by the clock actually advancing to the timeout (tokio only
+    // advances it when every task is blocked), so it would hang instead of passing if the
+    // detector were still spinning on stale readiness.
+    #[tokio::test(start_paused = true)]
+    async fn sniff_preface_gives_up_on_a_stalled_client_without_spinning() {
+        let (mut ours, theirs) = tokio::io::duplex(64);
+        let partial = &H2_PREFACE[..H2_PREFACE.len() - 1];
+        ours.write_all(partial).await.unwrap();
+
+        let start = tokio::time::Instant::now();
+        let (is_match, mut prefixed) =
+            sniff_preface(theirs, H2_PREFACE, Duration::from_millis(100))
+                .await
+                .unwrap();
+
+        assert!(!is_match);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        let mut buf = vec![0u8; partial.len()];
+        prefixed.read_exact(&mut buf).await.unwrap();
+        assert_eq!(buf, partial);
+    }
+}
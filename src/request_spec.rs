@@ -0,0 +1,178 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use crate::Size;
+use hyper::StatusCode;
+use std::{fmt, str::FromStr, time::Duration};
+
+/// Per-request overrides parsed from a request's query string.
+///
+/// Recognized parameters are `size`, `block`, `delay`, and `status`. Any parameter left
+/// unset here falls back to the server's configured defaults.
+///
+/// # Examples
+///
+/// ```
+/// use serve::RequestSpec;
+///
+/// # fn main() -> Result<(), serve::ParseRequestSpecError> {
+/// let spec: RequestSpec = "size=1mb&delay=500ms&status=503".parse()?;
+/// assert_eq!(*spec.size.unwrap(), 1_000_000);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RequestSpec {
+    /// Total size of the response body to generate.
+    pub size: Option<Size>,
+
+    /// Size of each chunk written while streaming the generated body.
+    pub block: Option<Size>,
+
+    /// How long to wait before responding.
+    pub delay: Option<Duration>,
+
+    /// The status code to respond with.
+    pub status: Option<StatusCode>,
+}
+
+impl FromStr for RequestSpec {
+    type Err = ParseRequestSpecError;
+
+    fn from_str(query: &str) -> Result<Self, Self::Err> {
+        let mut spec = RequestSpec::default();
+
+        // Accept both `&`-separated query strings and `,`-separated route specs.
+        for pair in query.split(['&', ',']) {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let value = parts.next().ok_or(RequestSpecErrorKind::MissingValue)?;
+
+            match key {
+                "size" => {
+                    spec.size = Some(value.parse().map_err(|_| RequestSpecErrorKind::InvalidSize)?)
+                }
+                "block" => {
+                    spec.block =
+                        Some(value.parse().map_err(|_| RequestSpecErrorKind::InvalidSize)?)
+                }
+                "delay" => {
+                    spec.delay = Some(
+                        humantime::parse_duration(value)
+                            .map_err(|_| RequestSpecErrorKind::InvalidDelay)?,
+                    )
+                }
+                "status" => {
+                    spec.status = Some(
+                        value
+                            .parse::<u16>()
+                            .ok()
+                            .and_then(|code| StatusCode::from_u16(code).ok())
+                            .ok_or(RequestSpecErrorKind::InvalidStatus)?,
+                    )
+                }
+                _ => return Err(RequestSpecErrorKind::UnknownParameter.into()),
+            }
+        }
+
+        Ok(spec)
+    }
+}
+
+/// Error returned by [`RequestSpec::from_str()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseRequestSpecError {
+    pub(crate) kind: RequestSpecErrorKind,
+}
+
+/// Error kind for [`ParseRequestSpecError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RequestSpecErrorKind {
+    /// A parameter was missing its `=value`.
+    MissingValue,
+
+    /// The `size` or `block` value was not a valid [`Size`](crate::Size).
+    InvalidSize,
+
+    /// The `delay` value was not a valid duration.
+    InvalidDelay,
+
+    /// The `status` value was not a valid HTTP status code.
+    InvalidStatus,
+
+    /// The parameter name was not recognized.
+    UnknownParameter,
+}
+
+impl From<RequestSpecErrorKind> for ParseRequestSpecError {
+    fn from(value: RequestSpecErrorKind) -> Self {
+        Self { kind: value }
+    }
+}
+
+impl fmt::Display for ParseRequestSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            RequestSpecErrorKind::MissingValue => f.write_str("missing value"),
+            RequestSpecErrorKind::InvalidSize => f.write_str("invalid size"),
+            RequestSpecErrorKind::InvalidDelay => f.write_str("invalid delay"),
+            RequestSpecErrorKind::InvalidStatus => f.write_str("invalid status"),
+            RequestSpecErrorKind::UnknownParameter => f.write_str("unknown parameter"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRequestSpecError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_spec_parse() {
+        let spec: RequestSpec = "size=1mb&delay=500ms&status=503&block=32kib"
+            .parse()
+            .unwrap();
+        assert_eq!(*spec.size.unwrap(), 1_000_000);
+        assert_eq!(*spec.block.unwrap(), 32 * 1024);
+        assert_eq!(spec.delay.unwrap(), Duration::from_millis(500));
+        assert_eq!(spec.status.unwrap(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn request_spec_parse_empty() {
+        let spec: RequestSpec = "".parse().unwrap();
+        assert_eq!(spec.size, None);
+        assert_eq!(spec.block, None);
+        assert_eq!(spec.delay, None);
+        assert_eq!(spec.status, None);
+    }
+
+    #[test]
+    fn request_spec_parse_missing_value() {
+        assert!(matches!(
+            "size".parse::<RequestSpec>(),
+            Err(err) if err.kind == RequestSpecErrorKind::MissingValue,
+        ));
+    }
+
+    #[test]
+    fn request_spec_parse_invalid_status() {
+        assert!(matches!(
+            "status=not-a-number".parse::<RequestSpec>(),
+            Err(err) if err.kind == RequestSpecErrorKind::InvalidStatus,
+        ));
+    }
+
+    #[test]
+    fn request_spec_parse_unknown_parameter() {
+        assert!(matches!(
+            "foo=bar".parse::<RequestSpec>(),
+            Err(err) if err.kind == RequestSpecErrorKind::UnknownParameter,
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright 2025 Heath Stewart.
+// Licensed under the MIT License. See LICENSE.txt in the project root for license information.
+
+use crate::{ParseRequestSpecError, RequestSpec};
+use std::{fmt, str::FromStr};
+
+/// Maps a path prefix to the [`RequestSpec`] used to answer requests under it.
+///
+/// Parsed from strings like `/big=>size=100mb,block=64kib`.
+#[derive(Clone, Debug)]
+pub struct Route {
+    /// The path prefix this route matches.
+    pub prefix: String,
+
+    /// The response overrides to apply to matching requests.
+    pub spec: RequestSpec,
+}
+
+impl FromStr for Route {
+    type Err = ParseRouteError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (prefix, spec) = s.split_once("=>").ok_or(RouteErrorKind::MissingSeparator)?;
+
+        if prefix.is_empty() {
+            return Err(RouteErrorKind::EmptyPrefix.into());
+        }
+
+        Ok(Route {
+            prefix: prefix.to_string(),
+            spec: spec.parse().map_err(RouteErrorKind::InvalidSpec)?,
+        })
+    }
+}
+
+/// Error returned by [`Route::from_str()`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseRouteError {
+    pub(crate) kind: RouteErrorKind,
+}
+
+/// Error kind for [`ParseRouteError`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RouteErrorKind {
+    /// The route was missing its `=>` separator between the path prefix and the spec.
+    MissingSeparator,
+
+    /// The path prefix before `=>` was empty.
+    EmptyPrefix,
+
+    /// The spec after `=>` could not be parsed as a [`RequestSpec`].
+    InvalidSpec(ParseRequestSpecError),
+}
+
+impl From<RouteErrorKind> for ParseRouteError {
+    fn from(kind: RouteErrorKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl fmt::Display for ParseRouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            RouteErrorKind::MissingSeparator => f.write_str("missing `=>` separator"),
+            RouteErrorKind::EmptyPrefix => f.write_str("empty path prefix"),
+            RouteErrorKind::InvalidSpec(err) => write!(f, "invalid route spec: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRouteError {}
+
+/// Picks the route with the longest matching prefix for `path`, if any.
+pub fn longest_match<'a>(routes: &'a [Route], path: &str) -> Option<&'a Route> {
+    routes
+        .iter()
+        .filter(|route| path.starts_with(route.prefix.as_str()))
+        .max_by_key(|route| route.prefix.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_parse() {
+        let route: Route = "/big=>size=100mb,block=64kib".parse().unwrap();
+        assert_eq!(route.prefix, "/big");
+        assert_eq!(*route.spec.size.unwrap(), 100_000_000);
+        assert_eq!(*route.spec.block.unwrap(), 64 * 1024);
+    }
+
+    #[test]
+    fn route_parse_missing_separator() {
+        assert!(matches!(
+            "/big".parse::<Route>(),
+            Err(err) if err.kind == RouteErrorKind::MissingSeparator,
+        ));
+    }
+
+    #[test]
+    fn route_parse_empty_prefix() {
+        assert!(matches!(
+            "=>size=1mb".parse::<Route>(),
+            Err(err) if err.kind == RouteErrorKind::EmptyPrefix,
+        ));
+    }
+
+    #[test]
+    fn longest_match_picks_most_specific() {
+        let routes = vec![
+            Route::from_str("/=>status=200").unwrap(),
+            Route::from_str("/big=>size=1mb").unwrap(),
+        ];
+        let route = longest_match(&routes, "/big/file.bin").unwrap();
+        assert_eq!(route.prefix, "/big");
+    }
+
+    #[test]
+    fn longest_match_breaks_ties_in_favor_of_the_last_route() {
+        // Mirrors how `main` concatenates `--config`'s routes with `--route`'s: appending the
+        // CLI's routes after the file's makes a same-length CLI prefix win ties.
+        let file_routes = vec![Route::from_str("/big=>size=1mb").unwrap()];
+        let cli_routes = vec![Route::from_str("/big=>size=2mb").unwrap()];
+        let routes: Vec<Route> = file_routes.into_iter().chain(cli_routes).collect();
+
+        let route = longest_match(&routes, "/big/file.bin").unwrap();
+        assert_eq!(*route.spec.size.unwrap(), 2_000_000);
+    }
+}
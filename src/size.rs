@@ -76,6 +76,51 @@ macro_rules! impl_from {
 impl_from!(u8 u16 u32 u64 u128);
 impl_from!(i8 i16 i32 i64 i128 isize);
 
+impl<'de> serde::Deserialize<'de> for Size {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct SizeVisitor;
+
+        impl serde::de::Visitor<'_> for SizeVisitor {
+            type Value = Size;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(r#"a size string like "32kb" or "1 mib", or an integer number of bytes"#)
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                v.parse().map_err(serde::de::Error::custom)
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(Size::from(v))
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                // TOML integers are signed and always deserialize via `visit_i64`, even when
+                // non-negative; accept non-negative values here instead of rejecting every TOML
+                // integer.
+                u64::try_from(v)
+                    .map(Size::from)
+                    .map_err(|_| serde::de::Error::custom("negative size"))
+            }
+        }
+
+        deserializer.deserialize_any(SizeVisitor)
+    }
+}
+
 impl FromStr for Size {
     type Err = ParseSizeError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -273,4 +318,37 @@ mod tests {
         assert_eq!(format!("{size}"), "1 B");
         assert_eq!(format!("{size:#}"), "1 B");
     }
+
+    #[test]
+    fn size_deserialize_string() {
+        let size: Size = serde_json::from_str(r#""1 mib""#).unwrap();
+        assert_eq!(*size, 1024 * 1024);
+    }
+
+    #[test]
+    fn size_deserialize_json_integer() {
+        let size: Size = serde_json::from_str("100").unwrap();
+        assert_eq!(*size, 100);
+    }
+
+    #[test]
+    fn size_deserialize_toml_integer() {
+        #[derive(serde::Deserialize)]
+        struct Doc {
+            size: Size,
+        }
+
+        let doc: Doc = toml::from_str("size = 100").unwrap();
+        assert_eq!(*doc.size, 100);
+    }
+
+    #[test]
+    fn size_deserialize_rejects_negative() {
+        #[derive(serde::Deserialize)]
+        struct Doc {
+            size: Size,
+        }
+
+        assert!(toml::from_str::<Doc>("size = -1").is_err());
+    }
 }